@@ -0,0 +1,179 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015 Aaron Loucks <aloucks+github@cofront.net>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use std::ptr;
+use std::slice::{Iter, IterMut};
+use std::mem;
+
+use crate::{Entity, EntityPool};
+
+/// A dense component storage that parallels an `EntityPool`'s layout.
+///
+/// `Components<T>` keeps a `Vec<T>` whose order mirrors the pool's own `entities` vector, so
+/// `pool.index_of(entity)` indexes directly into it. This turns `EntityPool` into a minimal
+/// dense ECS storage backbone: components stay contiguous in memory, giving cache-friendly
+/// iteration via [`iter`](Components::iter)/[`iter_mut`](Components::iter_mut).
+///
+/// Callers are responsible for keeping a `Components<T>` in lockstep with the `EntityPool` it
+/// parallels: insert a component only after creating the entity in the pool, and remove it
+/// before (not after) returning the entity, since `EntityPool::index_of` can no longer resolve
+/// a returned entity.
+#[derive(Debug, Clone)]
+pub struct Components<T> {
+    values: Vec<T>,
+}
+
+impl<T> Default for Components<T> {
+    #[inline(always)]
+    fn default() -> Components<T> {
+        Components::new()
+    }
+}
+
+impl<T> Components<T> {
+
+    /// Creates a new, empty `Components<T>`.
+    pub fn new() -> Components<T> {
+        Components { values: Vec::new() }
+    }
+
+    /// Creates a new, empty `Components<T>` that can hold `capacity` values without
+    /// reallocating.
+    pub fn with_capacity(capacity: usize) -> Components<T> {
+        Components { values: Vec::with_capacity(capacity) }
+    }
+
+    /// Inserts `value` for `entity`, resolving its dense index via `pool`.
+    ///
+    /// Returns the previously stored value, if `entity`'s index already held one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `entity` is not alive in `pool`, or if `entity`'s index is more than one past
+    /// the end of this storage (i.e. entities must be given components in the same order they
+    /// are created in the pool).
+    pub fn insert(&mut self, pool: &EntityPool, entity: Entity, value: T) -> Option<T> {
+        let index = pool.index_of(entity);
+        if index == self.values.len() {
+            self.values.push(value);
+            None
+        }
+        else {
+            Some(mem::replace(&mut self.values[index], value))
+        }
+    }
+
+    /// Returns the component for `entity`, or `None` if `entity` is not alive in `pool` or has
+    /// no component stored.
+    pub fn get(&self, pool: &EntityPool, entity: Entity) -> Option<&T> {
+        if pool.is_alive(entity) {
+            self.values.get(pool.index_of(entity))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the component for `entity`, or `None` if `entity` is not
+    /// alive in `pool` or has no component stored.
+    pub fn get_mut(&mut self, pool: &EntityPool, entity: Entity) -> Option<&mut T> {
+        if pool.is_alive(entity) {
+            self.values.get_mut(pool.index_of(entity))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Removes and returns the component for `entity`, swap-removing its slot exactly the way
+    /// `EntityPool::return_entity` swap-removes the entity itself.
+    ///
+    /// Must be called with `entity` still alive in `pool`, i.e. before calling
+    /// `pool.return_entity(entity)`.
+    pub fn remove(&mut self, pool: &EntityPool, entity: Entity) -> T {
+        let index = pool.index_of(entity);
+        self.values.swap_remove(index)
+    }
+
+    /// Overwrites the slot at `index` with `value` without dropping its previous contents.
+    ///
+    /// Only slots that already exist can be written; a newly created entity's index is always
+    /// one past the end of this storage, so its component is added with `insert` instead.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`. The previous contents of the slot are
+    /// overwritten without running `Drop`; callers must ensure they have already been dropped
+    /// or moved out, or the old value will leak.
+    pub unsafe fn write(&mut self, index: usize, value: T) {
+        debug_assert!(index < self.values.len());
+        let ptr = self.values.as_mut_ptr().add(index);
+        ptr::write(ptr, value);
+    }
+
+    /// Returns an iterator over the components, in dense storage order.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<T> {
+        self.values.iter()
+    }
+
+    /// Returns a mutable iterator over the components, in dense storage order.
+    #[inline(always)]
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        self.values.iter_mut()
+    }
+
+    /// Returns the number of components currently stored.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+}
+
+#[test]
+fn insert_get_and_remove_stay_in_lockstep_with_the_pool() {
+    use crate::EntityPool;
+
+    let mut pool = EntityPool::new();
+    let mut components = Components::new();
+
+    let (_, a) = pool.create_entity();
+    let (_, b) = pool.create_entity();
+    let (_, c) = pool.create_entity();
+    components.insert(&pool, a, "a");
+    components.insert(&pool, b, "b");
+    components.insert(&pool, c, "c");
+
+    assert_eq!(Some(&"a"), components.get(&pool, a));
+    assert_eq!(Some(&"b"), components.get(&pool, b));
+    assert_eq!(Some(&"c"), components.get(&pool, c));
+
+    // `remove` must swap-remove the same way `return_entity` does, so the two stay aligned
+    // when `b`'s slot is backfilled by the last entity (`c`).
+    assert_eq!("b", components.remove(&pool, b));
+    pool.return_entity(b);
+
+    assert_eq!(2, components.len());
+    assert_eq!(Some(&"a"), components.get(&pool, a));
+    assert_eq!(Some(&"c"), components.get(&pool, c));
+    assert_eq!(None, components.get(&pool, b));
+}
@@ -0,0 +1,281 @@
+// The MIT License (MIT)
+//
+// Copyright (c) 2015 Aaron Loucks <aloucks+github@cofront.net>
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::Entity;
+
+// Lists are carved out of `ListPool::data` in power-of-two size classes, so a handful of
+// common list lengths (children, adjacency, ...) share a small number of block sizes and can
+// be recycled LIFO instead of each owning its own heap allocation.
+const SIZE_CLASSES: [u32; 8] = [4, 8, 16, 32, 64, 128, 256, 512];
+
+// `EntityList`'s handle packs a size class and an arena offset into a single `u32`, so the
+// list itself stays 4 bytes inline (no separate length field) while `get`/`as_slice` can still
+// bound their scan of the block to its true capacity.
+const CLASS_BITS: u32 = 3;
+const CLASS_SHIFT: u32 = 32 - CLASS_BITS;
+const OFFSET_MASK: u32 = (1 << CLASS_SHIFT) - 1;
+
+// All bits set: an unreachable (class, offset) pair in practice, reserved to mean "no block
+// allocated yet", mirroring the all-ones sentinels `EntityPool` itself uses for invalid values.
+const EMPTY: u32 = std::u32::MAX;
+
+#[inline(always)]
+fn pack(class: u32, offset: u32) -> u32 {
+    debug_assert!((class as usize) < SIZE_CLASSES.len());
+    debug_assert!(offset <= OFFSET_MASK, "ListPool arena exhausted its offset space");
+    (class << CLASS_SHIFT) | offset
+}
+
+#[inline(always)]
+fn unpack(packed: u32) -> (usize, u32) {
+    ((packed >> CLASS_SHIFT) as usize, packed & OFFSET_MASK)
+}
+
+/// Arena that backs one or more [`EntityList`]s.
+///
+/// Lists are carved out of a single contiguous `Vec<Entity>` in size classes (4, 8, 16, ...
+/// entities). Freeing a list returns its block to the free list for its size class, so the
+/// next list that grows into that class reuses the block LIFO instead of allocating.
+#[derive(Debug, Clone)]
+pub struct ListPool {
+    data: Vec<Entity>,
+    free: Vec<Vec<u32>>,
+}
+
+impl Default for ListPool {
+    #[inline(always)]
+    fn default() -> ListPool {
+        ListPool::new()
+    }
+}
+
+impl ListPool {
+
+    /// Creates a new, empty `ListPool`.
+    pub fn new() -> ListPool {
+        ListPool {
+            data: Vec::new(),
+            free: vec![Vec::new(); SIZE_CLASSES.len()],
+        }
+    }
+
+    // Blocks reused from a free list are zeroed here, because `EntityList::push` frees the block
+    // it outgrows without clearing it, so that every allocated block, fresh or recycled,
+    // satisfies the same invariant: any slot
+    // beyond the list's true length holds `Entity::default()`, the end-of-data marker that
+    // `EntityList` scans for.
+    fn alloc(&mut self, class: usize) -> u32 {
+        let capacity = SIZE_CLASSES[class] as usize;
+        if let Some(offset) = self.free[class].pop() {
+            for slot in &mut self.data[offset as usize..offset as usize + capacity] {
+                *slot = Entity::default();
+            }
+            offset
+        }
+        else {
+            let offset = self.data.len() as u32;
+            self.data.resize(self.data.len() + capacity, Entity::default());
+            offset
+        }
+    }
+
+    /// Discards every list ever allocated from this pool at once, without visiting them
+    /// individually.
+    ///
+    /// Any [`EntityList`] still holding a handle into this pool reads garbage (stale or
+    /// since-reused slots) if used after this call, or panics if its block now lies past the
+    /// end of the arena; it is the caller's responsibility to drop or reset those handles too.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        for free in &mut self.free {
+            free.clear();
+        }
+    }
+}
+
+/// A compact 4-byte handle to a variable-length list of [`Entity`] values allocated from a
+/// [`ListPool`] arena, modeled on `cranelift-entity`'s `EntityList`.
+///
+/// Because the list's data lives in the pool rather than inline, an entity that owns (for
+/// example) a children or adjacency list pays only 4 bytes for it instead of a full `Vec`.
+/// Every method that reads or writes the list's contents takes the backing `&ListPool` /
+/// `&mut ListPool` explicitly.
+///
+/// A list that is no longer reachable (e.g. its owning entity was returned without calling
+/// [`clear`](EntityList::clear)) leaks its block in the pool until the whole pool is cleared via
+/// [`ListPool::clear`]. A list read from after its pool has been cleared returns garbage, or
+/// panics if its block lies past the end of the pool's arena. `Entity::default()` is reserved in-band as this list's unused-slot/end-of-data marker
+/// and cannot be stored as an element; [`push`](EntityList::push) panics if asked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityList {
+    index: u32,
+}
+
+impl Default for EntityList {
+    #[inline(always)]
+    fn default() -> EntityList {
+        EntityList { index: EMPTY }
+    }
+}
+
+impl EntityList {
+
+    /// Creates a new, empty list. No block is allocated from the pool until the first `push`.
+    pub fn new() -> EntityList {
+        EntityList::default()
+    }
+
+    /// Returns `true` if this list holds no block: it is new, or was emptied by
+    /// [`clear`](EntityList::clear) since its last `push`.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.index == EMPTY
+    }
+
+    /// Returns the number of entities in this list.
+    pub fn len(&self, pool: &ListPool) -> usize {
+        self.as_slice(pool).len()
+    }
+
+    /// Appends `value` to the end of this list, growing into the next size class (and copying
+    /// the list's existing entries) if its current block is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `Entity::default()`, which this module uses in-band as the
+    /// unused-slot/end-of-data marker; pushing it would silently truncate the list at that
+    /// point and hide every entity pushed after it. Also panics if the list would grow beyond
+    /// the largest size class.
+    pub fn push(&mut self, value: Entity, pool: &mut ListPool) {
+        assert!(value != Entity::default(), "Entity::default() is reserved as this list's end-of-data marker and cannot be stored in it");
+        if self.index == EMPTY {
+            let offset = pool.alloc(0);
+            pool.data[offset as usize] = value;
+            self.index = pack(0, offset);
+            return;
+        }
+        let (class, offset) = unpack(self.index);
+        let capacity = SIZE_CLASSES[class] as usize;
+        let start = offset as usize;
+        let len = pool.data[start..start + capacity].iter()
+            .position(|e| *e == Entity::default())
+            .unwrap_or(capacity);
+        if len < capacity {
+            pool.data[start + len] = value;
+            return;
+        }
+        let next_class = class + 1;
+        assert!(next_class < SIZE_CLASSES.len(), "EntityList exceeded the largest size class");
+        let next_offset = pool.alloc(next_class) as usize;
+        for i in 0..capacity {
+            pool.data[next_offset + i] = pool.data[start + i];
+        }
+        pool.data[next_offset + capacity] = value;
+        pool.free[class].push(offset);
+        self.index = pack(next_class as u32, next_offset as u32);
+    }
+
+    /// Returns the entity at `index` in this list, or `None` if `index` is out of bounds.
+    pub fn get(&self, index: usize, pool: &ListPool) -> Option<Entity> {
+        self.as_slice(pool).get(index).copied()
+    }
+
+    /// Returns this list's entities as a contiguous slice into the pool's arena.
+    pub fn as_slice<'a>(&self, pool: &'a ListPool) -> &'a [Entity] {
+        if self.index == EMPTY {
+            return &[];
+        }
+        let (class, offset) = unpack(self.index);
+        let capacity = SIZE_CLASSES[class] as usize;
+        let start = offset as usize;
+        let block = &pool.data[start..start + capacity];
+        let len = block.iter().position(|e| *e == Entity::default()).unwrap_or(capacity);
+        &block[..len]
+    }
+
+    /// Empties this list and returns its backing block to `pool`'s free list, so a future list
+    /// that grows into the same size class can reuse it.
+    pub fn clear(&mut self, pool: &mut ListPool) {
+        if self.index == EMPTY {
+            return;
+        }
+        let (class, offset) = unpack(self.index);
+        let capacity = SIZE_CLASSES[class] as usize;
+        let start = offset as usize;
+        for slot in &mut pool.data[start..start + capacity] {
+            *slot = Entity::default();
+        }
+        pool.free[class].push(offset);
+        self.index = EMPTY;
+    }
+}
+
+#[test]
+fn push_get_and_as_slice_survive_growth_across_size_classes() {
+    let mut entity_pool = crate::EntityPool::new();
+    let mut list_pool = ListPool::new();
+    let mut list = EntityList::new();
+    let mut entities = Vec::new();
+    for _ in 0..20 {
+        let (_, e) = entity_pool.create_entity();
+        entities.push(e);
+        list.push(e, &mut list_pool);
+    }
+    assert_eq!(20, list.len(&list_pool));
+    assert_eq!(entities.as_slice(), list.as_slice(&list_pool));
+    for (i, e) in entities.iter().enumerate() {
+        assert_eq!(Some(*e), list.get(i, &list_pool));
+    }
+    assert_eq!(None, list.get(20, &list_pool));
+}
+
+#[test]
+fn clear_allows_the_block_to_be_reused_without_leaking_stale_data() {
+    let mut entity_pool = crate::EntityPool::new();
+    let mut list_pool = ListPool::new();
+    let mut list = EntityList::new();
+    let mut entities = Vec::new();
+    for _ in 0..20 {
+        let (_, e) = entity_pool.create_entity();
+        entities.push(e);
+        list.push(e, &mut list_pool);
+    }
+    list.clear(&mut list_pool);
+    assert!(list.is_empty());
+    assert_eq!(0, list.len(&list_pool));
+    assert!(list.as_slice(&list_pool).is_empty());
+
+    // A fresh list reusing recycled blocks must not observe the previous occupant's data.
+    let mut other = EntityList::new();
+    for e in &entities[0..5] {
+        other.push(*e, &mut list_pool);
+    }
+    assert_eq!(&entities[0..5], other.as_slice(&list_pool));
+}
+
+#[test]
+#[should_panic(expected = "end-of-data marker")]
+fn push_rejects_the_reserved_sentinel_value() {
+    let mut list_pool = ListPool::new();
+    let mut list = EntityList::new();
+    list.push(crate::Entity::default(), &mut list_pool);
+}
@@ -25,56 +25,87 @@ use std::hash::Hash;
 use std::hash::Hasher;
 use std::fmt::{self, Debug};
 use std::ops::Index;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::num::NonZeroU32;
+use std::collections::TryReserveError;
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub struct Entity(u64);
+mod components;
+pub use components::Components;
+
+mod entity_list;
+pub use entity_list::{EntityList, ListPool};
 
-const INVALID_ID: u64 = std::u64::MAX;
+// The live generation range is `1..=(INVALID_GENERATION - 1)`. `INVALID_GENERATION` is never
+// issued to a created entity; it is reserved for `Entity::default()` so that it can never
+// collide with a real, live entity. Encoding the generation as a `NonZeroU32` additionally
+// gives the compiler a niche to store in, so `Option<Entity>` is the same size as `Entity`.
+const INVALID_KEY: u32 = std::u32::MAX;
+const INVALID_GENERATION: u32 = std::u32::MAX;
 const INVALID_INDEX: u32 = std::u32::MAX;
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Entity {
+    key: u32,
+    gen: NonZeroU32,
+}
+
 impl Default for Entity {
     #[inline(always)]
     fn default() -> Entity {
-        Entity(INVALID_ID)
+        Entity {
+            key: INVALID_KEY,
+            gen: NonZeroU32::new(INVALID_GENERATION).unwrap(),
+        }
     }
 }
 
 impl Debug for Entity {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f, "Entity({{id: {}, key: {}, gen: {}}})", self.0, self.key(), self.gen())
+        write!(f, "Entity({{key: {}, gen: {}}})", self.key(), self.gen())
     }
 }
 
 impl Hash for Entity {
     #[inline(always)]
     fn hash<H>(&self, state: &mut H) where H: Hasher {
-        state.write_u64(self.0)
+        state.write_u32(self.key);
+        state.write_u32(self.gen.get());
     }
 }
 
 impl Entity {
     #[inline(always)]
     fn from_key_and_gen(key: u32, gen: u32) -> Entity {
-        Entity(((key as u64) << 32) | (gen as u64))
+        debug_assert_ne!(gen, 0, "generation 0 is reserved by NonZeroU32's niche");
+        debug_assert_ne!(gen, INVALID_GENERATION, "generation must not collide with Entity::default()");
+        Entity { key, gen: NonZeroU32::new(gen).expect("generation must be nonzero") }
     }
 
     #[inline(always)]
     fn key(&self) -> u32 {
-        (self.0 >> 32) as u32
+        self.key
     }
 
     #[inline(always)]
     fn gen(&self) -> u32 {
-        (self.0 & 0xFFFFFFFF) as u32
+        self.gen.get()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct EntityPool {
     entities: Vec<Entity>,
     entities_free: Vec<Entity>,
     entity_index: Vec<u32>, // entity_index[entity.key] => index; entities[index as usize]
     next_entity_key: u32,
+    pending_returns: Rc<RefCell<Vec<Entity>>>,
+    // Counts down from `entities_free.len()` as entities are reserved via `reserve_entity`.
+    // A positive value `n` reserves `entities_free[n - 1]`; a value `n <= 0` reserves the
+    // brand-new key `next_entity_key + (-n)`. Reset to `entities_free.len()` by `flush`.
+    reserve_cursor: AtomicI64,
+    retired: usize,
 }
 
 impl Default for EntityPool {
@@ -84,6 +115,22 @@ impl Default for EntityPool {
     }
 }
 
+impl Clone for EntityPool {
+    fn clone(&self) -> EntityPool {
+        EntityPool {
+            entities: self.entities.clone(),
+            entities_free: self.entities_free.clone(),
+            entity_index: self.entity_index.clone(),
+            next_entity_key: self.next_entity_key,
+            // A clone must not share its reclaim queue with `self`: an `EntityLease` dropped
+            // against one pool has no business returning its entity into the other.
+            pending_returns: Rc::new(RefCell::new(Vec::new())),
+            reserve_cursor: AtomicI64::new(self.reserve_cursor.load(Ordering::Relaxed)),
+            retired: self.retired,
+        }
+    }
+}
+
 impl EntityPool {
 
     /// Creates a new, empty `EntityPool`.
@@ -104,7 +151,10 @@ impl EntityPool {
             entities: Vec::with_capacity(create_capacity),
             entities_free: Vec::with_capacity(return_capacity),
             entity_index: Vec::with_capacity(create_capacity),
-            next_entity_key: 0
+            next_entity_key: 0,
+            pending_returns: Rc::new(RefCell::new(Vec::new())),
+            reserve_cursor: AtomicI64::new(0),
+            retired: 0,
         }
     }
 
@@ -113,16 +163,20 @@ impl EntityPool {
     /// Returns the `Entity` and it's current `index`. The index is only guaranteed to remain
     /// valid until the next call to `return_entity`.
     pub fn create_entity(&mut self) -> (usize, Entity) {
+        // Recycled entities in `entities_free` already carry the generation they should be
+        // issued with; `return_entity` bumps (and, on overflow, retires) the generation
+        // when the entity is returned, not when it is recreated here.
         let (key, gen) = match self.entities_free.pop() {
             Some(entity) => {
-                (entity.key(), entity.gen().wrapping_add(1))
+                (entity.key(), entity.gen())
             },
             None => {
                 let key = self.next_entity_key;
                 self.next_entity_key = key + 1;
-                (key, 0)
+                (key, 1)
             }
         };
+        self.sync_reserve_cursor();
         let entity = Entity::from_key_and_gen(key, gen);
         let index = self.entities.len() as u32;
         self.entities.push(entity);
@@ -136,9 +190,30 @@ impl EntityPool {
         (index as usize, entity)
     }
 
+    /// Like `create_entity`, but returns an error instead of aborting if the required
+    /// allocation fails, for use in capacity-constrained environments that must never abort
+    /// on OOM.
+    pub fn try_create_entity(&mut self) -> Result<(usize, Entity), TryReserveError> {
+        let key = match self.entities_free.last() {
+            Some(entity) => entity.key(),
+            None => self.next_entity_key,
+        };
+        self.entities.try_reserve(1)?;
+        if key as usize == self.entity_index.len() {
+            self.entity_index.try_reserve(1)?;
+        }
+        Ok(self.create_entity())
+    }
+
     /// Release ownership of the `entity`, allowing for it to be recycled. A recycled entity will
     /// have it's internal generation incremented, yielding a new, unique entity.
     ///
+    /// If incrementing the generation would collide with the reserved invalid generation, the
+    /// entity's key is retired instead of being recycled: it is never reissued by
+    /// `create_entity`/`reserve_entity` again, which prevents a stale `Entity` handle from a
+    /// prior, exhausted generation from spuriously comparing alive against an unrelated entity
+    /// that reused the same key. See [`len_retired`](EntityPool::len_retired).
+    ///
     /// Entities are stored in contiguous memory. When an entity is returned, the last entity is
     /// swaped into the returned entity's slot; thus indexes retrieved prior to returning an
     /// entity are potentially invalidated.
@@ -182,7 +257,15 @@ impl EntityPool {
         let key = entity.key();
         let index = self.entity_index[key as usize];
         debug_assert_eq!(entity.gen(), self.entities[index as usize].gen());
-        self.entities_free.push(entity);
+        match entity.gen().checked_add(1) {
+            Some(next_gen) if next_gen < INVALID_GENERATION => {
+                self.entities_free.push(Entity::from_key_and_gen(key, next_gen));
+                self.sync_reserve_cursor();
+            },
+            _ => {
+                self.retired += 1;
+            }
+        }
         self.entities.swap_remove(index as usize);
         self.entity_index[key as usize] = INVALID_INDEX;
         match self.entities.get(index as usize) {
@@ -191,19 +274,173 @@ impl EntityPool {
         };
     }
 
+    /// Creates a unique entity and wraps it in an RAII guard that returns it to this pool
+    /// automatically when dropped.
+    ///
+    /// Because `return_entity` requires `&mut EntityPool`, the guard cannot return its entity
+    /// the instant it is dropped; instead it queues the entity for reclamation. Call
+    /// [`reclaim`](EntityPool::reclaim) (e.g. once per frame) to drain that queue and make the
+    /// returned entities available for recycling again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use entitypool::EntityPool;
+    ///
+    /// let mut pool = EntityPool::new();
+    /// let e1 = {
+    ///     let (_, lease) = pool.lease_entity();
+    ///     lease.entity()
+    /// }; // lease dropped here, entity queued for reclamation
+    /// assert!(pool.is_alive(e1));
+    /// pool.reclaim();
+    /// assert!(!pool.is_alive(e1));
+    /// ```
+    pub fn lease_entity(&mut self) -> (usize, EntityLease) {
+        let (index, entity) = self.create_entity();
+        let lease = EntityLease {
+            entity,
+            pending_returns: self.pending_returns.clone(),
+        };
+        (index, lease)
+    }
+
+    /// Drains entities queued by dropped [`EntityLease`] guards back into the free list.
+    ///
+    /// Returns the number of entities reclaimed.
+    pub fn reclaim(&mut self) -> usize {
+        let pending: Vec<Entity> = self.pending_returns.borrow_mut().drain(..).collect();
+        let count = pending.len();
+        for entity in pending {
+            self.return_entity(entity);
+        }
+        count
+    }
+
+    // `reserve_cursor` counts down from `entities_free.len()` *as of the last time it was
+    // brought in sync*, so that `reserve_entity` can derive each outstanding reservation's
+    // identity from a stable snapshot. `create_entity`/`return_entity` mutate `entities_free`
+    // directly (outside of any reservation), so they must call this immediately after to keep
+    // that snapshot from going stale; skipping it would let `flush` mistake an ordinary
+    // create/return for an outstanding reservation. Only valid while no reservations are
+    // currently outstanding, which `reserve_entity`'s own doc already requires of callers.
+    fn sync_reserve_cursor(&mut self) {
+        self.reserve_cursor = AtomicI64::new(self.entities_free.len() as i64);
+    }
+
+    /// Reserves a unique entity from a shared `&self`, without requiring exclusive access to
+    /// the pool.
+    ///
+    /// This allows spawning entities while concurrently iterating [`iter`](EntityPool::iter),
+    /// e.g. from within a command buffer built up during a single-threaded query. `EntityPool`
+    /// is not `Send`/`Sync` (it holds an `Rc`), so this does not make it safe to call
+    /// `reserve_entity` from multiple threads at once. Reserved entities are considered alive
+    /// (`is_alive` and `index_of` both account for them) but are not yet present in the dense
+    /// `entities` storage; call [`flush`](EntityPool::flush) to materialize all outstanding
+    /// reservations.
+    ///
+    /// Reserving entities while also calling `create_entity` or `return_entity` before the next
+    /// `flush` is not supported, since both paths draw from `entities_free`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use entitypool::EntityPool;
+    ///
+    /// let mut pool = EntityPool::new();
+    /// let e1 = pool.reserve_entity();
+    /// assert!(pool.is_alive(e1));
+    /// pool.flush();
+    /// assert_eq!(0, pool.index_of(e1));
+    /// ```
+    pub fn reserve_entity(&self) -> Entity {
+        // Entries in `entities_free` already carry the generation they should be issued with
+        // (see `return_entity`), so reservation never needs to bump the generation itself.
+        let n = self.reserve_cursor.fetch_sub(1, Ordering::Relaxed);
+        if n > 0 {
+            self.entities_free[(n - 1) as usize]
+        }
+        else {
+            let extra = (-n) as u32;
+            Entity::from_key_and_gen(self.next_entity_key + extra, 1)
+        }
+    }
+
+    /// Materializes every entity reserved via `reserve_entity` since the last flush into the
+    /// dense storage, and resets the reservation cursor.
+    pub fn flush(&mut self) {
+        let starting_free = self.entities_free.len() as i64;
+        let outstanding = starting_free - self.reserve_cursor.load(Ordering::Relaxed);
+        if outstanding <= 0 {
+            return;
+        }
+        let outstanding = outstanding as usize;
+        for i in 0..outstanding {
+            let entity = self.pending_reservation_at(i);
+            let key = entity.key();
+            let index = self.entities.len() as u32;
+            self.entities.push(entity);
+            if key as usize != self.entity_index.len() {
+                self.entity_index[key as usize] = index;
+            }
+            else {
+                debug_assert_eq!(key as usize, self.entity_index.len());
+                self.entity_index.push(index);
+            }
+        }
+        let reused = outstanding.min(starting_free as usize);
+        let new_len = self.entities_free.len() - reused;
+        self.entities_free.truncate(new_len);
+        self.next_entity_key += (outstanding - reused) as u32;
+        self.reserve_cursor = AtomicI64::new(self.entities_free.len() as i64);
+    }
+
+    /// Returns the number of entities reserved via `reserve_entity` that have not yet been
+    /// materialized by `flush`.
+    fn pending_reservations(&self) -> usize {
+        let starting_free = self.entities_free.len() as i64;
+        let outstanding = starting_free - self.reserve_cursor.load(Ordering::Relaxed);
+        outstanding.max(0) as usize
+    }
+
+    /// Recomputes the `i`-th outstanding reservation without committing it, using the same
+    /// derivation as `reserve_entity`.
+    fn pending_reservation_at(&self, i: usize) -> Entity {
+        let n = self.entities_free.len() as i64 - i as i64;
+        if n > 0 {
+            self.entities_free[(n - 1) as usize]
+        }
+        else {
+            let extra = (-n) as u32;
+            Entity::from_key_and_gen(self.next_entity_key + extra, 1)
+        }
+    }
+
     /// Returns the current `index` of the given `entity`, which is only guaranteed to remain
     /// valid until the next call to `return_entity`.
     ///
+    /// A reserved-but-unflushed entity's index is the position it will occupy once `flush` is
+    /// called.
+    ///
     /// # Panics
     ///
     /// Querying the status of an entity from another pool results in undefined behavior.
-    #[inline(always)]
     pub fn index_of(&self, entity: Entity) -> usize {
         debug_assert!(entity != Entity::default());
         let key = entity.key();
-        let index = self.entity_index[key as usize] as usize;
-        debug_assert_eq!(entity.gen(), self.entities[index as usize].gen());
-        index
+        if (key as usize) < self.entity_index.len() {
+            let index = self.entity_index[key as usize];
+            if index != INVALID_INDEX {
+                debug_assert_eq!(entity.gen(), self.entities[index as usize].gen());
+                return index as usize;
+            }
+        }
+        for i in 0..self.pending_reservations() {
+            if self.pending_reservation_at(i) == entity {
+                return self.entities.len() + i;
+            }
+        }
+        panic!("entity is not alive in this pool");
     }
 
     /// Returns the current `entity` at the given `index`.
@@ -216,7 +453,8 @@ impl EntityPool {
         self.entities[index]
     }
 
-    /// Returns `true` if this entity has not been returned.
+    /// Returns `true` if this entity has not been returned. Entities reserved via
+    /// `reserve_entity` but not yet materialized by `flush` are considered alive.
     ///
     /// # Panics
     ///
@@ -224,14 +462,16 @@ impl EntityPool {
     pub fn is_alive(&self, entity: Entity) -> bool {
         debug_assert!(entity != Entity::default());
         let key = entity.key();
-        let index = self.entity_index[key as usize];
-        if index != INVALID_INDEX {
-            let other = self.entities[index as usize];
-            key == other.key() && entity.gen() == other.gen()
-        }
-        else {
-            false
+        if (key as usize) < self.entity_index.len() {
+            let index = self.entity_index[key as usize];
+            if index != INVALID_INDEX {
+                let other = self.entities[index as usize];
+                if key == other.key() && entity.gen() == other.gen() {
+                    return true;
+                }
+            }
         }
+        (0..self.pending_reservations()).any(|i| self.pending_reservation_at(i) == entity)
     }
 
     /// Returns an iterator to the live entities. The `Enumerate` of the returned iterator will
@@ -264,6 +504,13 @@ impl EntityPool {
         self.entities_free.clear();
         self.entity_index.clear();
         self.next_entity_key = 0;
+        // Any entity queued here by a dropped `EntityLease` belongs to the key space being
+        // discarded; keeping it around would return it into the reset pool the next time
+        // `reclaim` runs, potentially evicting an unrelated, newly-created entity that happens
+        // to recycle the same key.
+        self.pending_returns.borrow_mut().clear();
+        self.reserve_cursor = AtomicI64::new(0);
+        self.retired = 0;
     }
 
     /// Reserves capacity for at least `additional` more entities to be created without
@@ -279,6 +526,17 @@ impl EntityPool {
         self.entities_free.reserve(additional);
     }
 
+    /// Like `reserve`, but returns an error instead of aborting if allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.entities.try_reserve(additional)?;
+        self.entity_index.try_reserve(additional)
+    }
+
+    /// Like `reserve_returned`, but returns an error instead of aborting if allocation fails.
+    pub fn try_reserve_returned(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.entities_free.try_reserve(additional)
+    }
+
     /// Shrinks the capacity of this pool as much as possible.
     pub fn shrink_to_fit(&mut self) {
         self.entities.shrink_to_fit();
@@ -299,6 +557,13 @@ impl EntityPool {
         self.entities_free.len()
     }
 
+    /// Returns the number of entity keys permanently retired because their generation counter
+    /// was exhausted. Retired keys are never reissued by `create_entity` or `reserve_entity`.
+    #[inline(always)]
+    pub fn len_retired(&self) -> usize {
+        self.retired
+    }
+
     /// Returns the number of entities that this pool can create without reallocation.
     #[inline(always)]
     pub fn capacity(&self) -> usize {
@@ -313,6 +578,32 @@ impl EntityPool {
     }
 }
 
+/// An RAII guard around an `Entity` that automatically queues it for return to its
+/// `EntityPool` when dropped. Created via [`EntityPool::lease_entity`].
+///
+/// The entity is not returned synchronously on drop, since `return_entity` requires
+/// `&mut EntityPool`, which the lease has no way to guarantee access to. Call
+/// [`EntityPool::reclaim`] to drain dropped leases back into the pool's free list.
+#[derive(Debug)]
+pub struct EntityLease {
+    entity: Entity,
+    pending_returns: Rc<RefCell<Vec<Entity>>>,
+}
+
+impl EntityLease {
+    /// Returns the leased `Entity`.
+    #[inline(always)]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+}
+
+impl Drop for EntityLease {
+    fn drop(&mut self) {
+        self.pending_returns.borrow_mut().push(self.entity);
+    }
+}
+
 impl Index<u32> for EntityPool {
     type Output = Entity;
     /// Returns the `entity` at the given `index`.
@@ -331,6 +622,128 @@ impl Index<Entity> for EntityPool {
     }
 }
 
+/// `Serialize`/`Deserialize` impls for [`Entity`] and [`EntityPool`], enabled with the
+/// `enable-serde` feature.
+///
+/// `Entity` round-trips as its packed `(key, gen)` representation. `EntityPool` round-trips
+/// `entities`, `entities_free`, `entity_index`, and `next_entity_key` so that a deserialized
+/// pool continues issuing keys and recycling generations exactly where it left off; transient
+/// bookkeeping (`reserve_entity` cursor, `lease_entity` reclamation queue, retired-key count) is
+/// not part of the saved state and restarts fresh. Deserializing validates that the pool's
+/// invariants hold and returns an error rather than producing a corrupt pool.
+#[cfg(feature = "enable-serde")]
+mod serde_impl {
+    use super::{Entity, EntityPool, INVALID_INDEX};
+    use serde::de::Error as DeError;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::AtomicI64;
+
+    impl Serialize for Entity {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            (self.key, self.gen).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Entity {
+        fn deserialize<D>(deserializer: D) -> Result<Entity, D::Error> where D: Deserializer<'de> {
+            let (key, gen) = Deserialize::deserialize(deserializer)?;
+            Ok(Entity { key, gen })
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct EntityPoolData {
+        entities: Vec<Entity>,
+        entities_free: Vec<Entity>,
+        entity_index: Vec<u32>,
+        next_entity_key: u32,
+    }
+
+    impl Serialize for EntityPool {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            EntityPoolData {
+                entities: self.entities.clone(),
+                entities_free: self.entities_free.clone(),
+                entity_index: self.entity_index.clone(),
+                next_entity_key: self.next_entity_key,
+            }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for EntityPool {
+        fn deserialize<D>(deserializer: D) -> Result<EntityPool, D::Error> where D: Deserializer<'de> {
+            let data = EntityPoolData::deserialize(deserializer)?;
+
+            if data.entities.len() != data.entity_index.iter().filter(|&&i| i != INVALID_INDEX).count() {
+                return Err(DeError::custom("entity_index live count does not match entities length"));
+            }
+
+            let mut seen = vec![false; data.entities.len()];
+            for (key, &index) in data.entity_index.iter().enumerate() {
+                if index == INVALID_INDEX {
+                    continue;
+                }
+                let index = index as usize;
+                if index >= data.entities.len() || seen[index] {
+                    return Err(DeError::custom("entity_index contains a duplicate or out-of-range index"));
+                }
+                if data.entities[index].key() as usize != key {
+                    return Err(DeError::custom("entity_index does not agree with the entity stored at its index"));
+                }
+                seen[index] = true;
+            }
+
+            Ok(EntityPool {
+                reserve_cursor: AtomicI64::new(data.entities_free.len() as i64),
+                entities: data.entities,
+                entities_free: data.entities_free,
+                entity_index: data.entity_index,
+                next_entity_key: data.next_entity_key,
+                pending_returns: Rc::new(RefCell::new(Vec::new())),
+                retired: 0,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "enable-serde")]
+#[test]
+fn pool_round_trips_through_serde_and_keeps_recycling_where_it_left_off() {
+    let mut pool = EntityPool::new();
+    let (_, a) = pool.create_entity();
+    let (_, b) = pool.create_entity();
+    let (_, c) = pool.create_entity();
+    pool.return_entity(a);
+
+    let json = serde_json::to_string(&pool).unwrap();
+    let mut restored: EntityPool = serde_json::from_str(&json).unwrap();
+
+    assert!(!restored.is_alive(a));
+    assert!(restored.is_alive(b));
+    assert!(restored.is_alive(c));
+
+    // The restored pool must still recycle `a`'s key at the next-higher generation, exactly as
+    // the original pool would have.
+    let (_, reused) = restored.create_entity();
+    assert_eq!(a.key(), reused.key());
+    assert_ne!(a, reused);
+}
+
+#[cfg(feature = "enable-serde")]
+#[test]
+fn deserialize_rejects_a_pool_whose_entity_index_disagrees_with_its_entities() {
+    let json = serde_json::json!({
+        "entities": [[0u32, 1u32]],
+        "entities_free": [],
+        "entity_index": [1u32],
+        "next_entity_key": 1u32
+    });
+    let result: Result<EntityPool, _> = serde_json::from_value(json);
+    assert!(result.is_err());
+}
+
 #[test]
 fn it_works() {
     let mut pool = EntityPool::new();
@@ -384,3 +797,99 @@ fn it_works() {
     }
     assert_eq!(5, count);
 }
+
+#[test]
+fn entity_is_niche_optimized() {
+    use std::mem::size_of;
+    assert_eq!(size_of::<Entity>(), size_of::<Option<Entity>>());
+}
+
+#[test]
+fn try_reserve_lets_try_create_entity_avoid_reallocating() {
+    let mut pool = EntityPool::new();
+    pool.try_reserve(10).unwrap();
+    let capacity = pool.capacity();
+    assert!(capacity >= 10);
+    for _ in 0..10 {
+        pool.try_create_entity().unwrap();
+        assert_eq!(capacity, pool.capacity());
+    }
+}
+
+#[test]
+fn returning_an_entity_at_the_last_valid_generation_retires_its_key() {
+    let mut pool = EntityPool::new();
+    let (index, e) = pool.create_entity();
+    let key = e.key();
+    // Jump straight to the last valid generation rather than looping ~u32::MAX times.
+    let near_max = Entity::from_key_and_gen(key, INVALID_GENERATION - 1);
+    pool.entities[index] = near_max;
+    assert_eq!(0, pool.len_retired());
+    pool.return_entity(near_max);
+    assert_eq!(1, pool.len_retired());
+    assert_eq!(0, pool.len_returned());
+    let (_, reused) = pool.create_entity();
+    assert_ne!(key, reused.key());
+}
+
+#[test]
+fn reset_discards_leases_queued_before_it() {
+    let mut pool = EntityPool::new();
+    {
+        let (_, lease) = pool.lease_entity();
+        drop(lease); // queues the entity for reclamation
+    }
+    pool.reset();
+    let (_, e) = pool.create_entity(); // recycles the same key the stale queue entry names
+    assert_eq!(0, pool.reclaim());
+    assert!(pool.is_alive(e));
+}
+
+#[test]
+fn clone_does_not_share_its_reclaim_queue() {
+    let mut pool = EntityPool::new();
+    let (_, lease) = pool.lease_entity();
+    let mut clone = pool.clone();
+    drop(lease); // queues the entity for reclamation against `pool`, not `clone`
+    assert_eq!(0, clone.reclaim());
+    assert_eq!(1, pool.reclaim());
+}
+
+#[test]
+fn flush_does_not_resurrect_entities_returned_without_a_reservation() {
+    let mut pool = EntityPool::new();
+    let (_, a) = pool.create_entity();
+    pool.return_entity(a);
+    pool.flush(); // no `reserve_entity` call at all; flush must be a no-op
+    assert_eq!(0, pool.iter().count());
+    assert!(!pool.is_alive(a));
+}
+
+#[test]
+fn flush_materializes_exactly_the_reservations_that_were_made() {
+    let mut pool = EntityPool::new();
+    let (_, a) = pool.create_entity();
+    let (_, b) = pool.create_entity();
+    let (_, c) = pool.create_entity();
+    pool.return_entity(a);
+    pool.return_entity(b);
+    let reserved = pool.reserve_entity();
+    pool.flush();
+    // 3 created, 2 returned, 1 reserved: exactly 2 should be alive, and the reservation must
+    // not have resurrected both returned entities.
+    assert_eq!(2, pool.iter().count());
+    assert!(pool.is_alive(c));
+    assert!(pool.is_alive(reserved));
+    assert!(!pool.is_alive(a) || !pool.is_alive(b));
+}
+
+#[test]
+fn reserve_entity_after_create_entity_drains_the_free_list_stays_in_bounds() {
+    let mut pool = EntityPool::new();
+    let (_, a) = pool.create_entity();
+    pool.return_entity(a);
+    pool.create_entity(); // drains the single free-list entry `return_entity` just queued
+    let reserved = pool.reserve_entity(); // must not index out of bounds into an empty free list
+    pool.flush();
+    assert!(pool.is_alive(reserved));
+}